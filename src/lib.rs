@@ -1,5 +1,18 @@
-use std::mem::MaybeUninit;
-use std::os::raw::c_int;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+use core::ffi::c_int;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops;
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+#[cfg(all(feature = "serde", feature = "alloc"))]
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 
 #[link(name = "mcl", kind = "static")]
 #[link(name = "mclshe384_256", kind = "static")]
@@ -28,6 +41,32 @@ extern "C" {
 	fn sheMulG2(c: *mut CipherTextG2, x: *const CipherTextG2, y: i64) -> c_int;
 	fn sheMulGT(c: *mut CipherTextGT, x: *const CipherTextGT, y: i64) -> c_int;
 	fn sheMul(c: *mut CipherTextGT, x: *const CipherTextG1, y: *const CipherTextG2) -> c_int;
+
+	// serialize
+	#[cfg(feature = "alloc")]
+	fn sheSecretKeySerialize(buf: *mut u8, maxBufSize: usize, sec: *const SecretKey) -> usize;
+	fn sheSecretKeyDeserialize(sec: *mut SecretKey, buf: *const u8, bufSize: usize) -> usize;
+	#[cfg(feature = "alloc")]
+	fn shePublicKeySerialize(buf: *mut u8, maxBufSize: usize, pubkey: *const PublicKey) -> usize;
+	fn shePublicKeyDeserialize(pubkey: *mut PublicKey, buf: *const u8, bufSize: usize) -> usize;
+	#[cfg(feature = "alloc")]
+	fn sheCipherTextG1Serialize(buf: *mut u8, maxBufSize: usize, c: *const CipherTextG1) -> usize;
+	fn sheCipherTextG1Deserialize(c: *mut CipherTextG1, buf: *const u8, bufSize: usize) -> usize;
+	#[cfg(feature = "alloc")]
+	fn sheCipherTextG2Serialize(buf: *mut u8, maxBufSize: usize, c: *const CipherTextG2) -> usize;
+	fn sheCipherTextG2Deserialize(c: *mut CipherTextG2, buf: *const u8, bufSize: usize) -> usize;
+	#[cfg(feature = "alloc")]
+	fn sheCipherTextGTSerialize(buf: *mut u8, maxBufSize: usize, c: *const CipherTextGT) -> usize;
+	fn sheCipherTextGTDeserialize(c: *mut CipherTextGT, buf: *const u8, bufSize: usize) -> usize;
+
+	// baby-step/giant-step discrete-log table used by sheDecG1/G2/GT
+	fn sheSetRangeForDLP(hashSize: usize) -> c_int;
+	fn sheSetTryNum(tryNum: usize) -> c_int;
+	#[cfg(feature = "alloc")]
+	fn sheSaveTableForDLP(buf: *mut u8, maxBufSize: usize) -> usize;
+	fn sheLoadTableForDLP(buf: *const u8, bufSize: usize) -> usize;
+
+	fn mclBnFr_setHashOf(x: *mut Fr, buf: *const u8, bufSize: usize) -> c_int;
 }
 
 #[allow(non_camel_case_types)]
@@ -46,15 +85,26 @@ pub enum CurveType {
 
 const MCLBN_FP_UNIT_SIZE: usize = 6;
 const MCLBN_FR_UNIT_SIZE: usize = 4;
+
+// Sizes in 64-bit units, only consumed by the alloc-gated `serialize()` buffer sizing below.
+#[cfg(feature = "alloc")]
 const FR_SIZE : usize = MCLBN_FR_UNIT_SIZE;
+#[cfg(feature = "alloc")]
 const G1_SIZE : usize = MCLBN_FP_UNIT_SIZE * 3;
+#[cfg(feature = "alloc")]
 const G2_SIZE : usize = MCLBN_FP_UNIT_SIZE * 6;
+#[cfg(feature = "alloc")]
 const GT_SIZE : usize = MCLBN_FP_UNIT_SIZE * 12;
 
+#[cfg(feature = "alloc")]
 const SEC_SIZE : usize = FR_SIZE * 2;
+#[cfg(feature = "alloc")]
 const PUB_SIZE : usize = G1_SIZE + G2_SIZE;
+#[cfg(feature = "alloc")]
 const G1_CIPHER_SIZE : usize= G1_SIZE * 2;
+#[cfg(feature = "alloc")]
 const G2_CIPHER_SIZE : usize= G2_SIZE * 2;
+#[cfg(feature = "alloc")]
 const GT_CIPHER_SIZE : usize= GT_SIZE * 4;
 const MCLBN_COMPILED_TIME_VAR: c_int =
     (MCLBN_FR_UNIT_SIZE * 10 + MCLBN_FP_UNIT_SIZE) as c_int;
@@ -66,7 +116,7 @@ macro_rules! common_impl {
                 Default::default()
             }
             pub unsafe fn uninit() -> $t {
-                std::mem::MaybeUninit::uninit().assume_init()
+                MaybeUninit::uninit().assume_init()
             }
             pub fn clear(&mut self) {
                 *self = <$t>::zero()
@@ -78,11 +128,18 @@ macro_rules! common_impl {
 macro_rules! serialize_impl {
     ($t:ty, $size:expr, $serialize_fn:ident, $deserialize_fn:ident) => {
         impl $t {
-            pub fn deserialize(&mut self, buf: &[u8]) -> bool {
-                unsafe { $deserialize_fn(self, buf.as_ptr(), buf.len()) > 0 }
+            pub fn deserialize(&mut self, buf: &[u8]) -> Result<(), SheError> {
+                let n = unsafe { $deserialize_fn(self, buf.as_ptr(), buf.len()) };
+                if n > 0 {
+                    Ok(())
+                } else {
+                    Err(SheError::DeserializeFailed)
+                }
             }
+            #[cfg(feature = "alloc")]
             pub fn serialize(&self) -> Vec<u8> {
-                let size = unsafe { $size } as usize;
+                // `$size` counts 64-bit units; mcl wants the buffer size in bytes.
+                let size = $size * core::mem::size_of::<u64>();
                 let mut buf: Vec<u8> = Vec::with_capacity(size);
                 let n: usize;
                 unsafe {
@@ -100,6 +157,34 @@ macro_rules! serialize_impl {
     };
 }
 
+macro_rules! serde_impl {
+    ($t:ty) => {
+        #[cfg(all(feature = "serde", feature = "alloc"))]
+        impl Serialize for $t {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_bytes(&self.serialize())
+            }
+        }
+
+        #[cfg(all(feature = "serde", feature = "alloc"))]
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                let buf = Vec::<u8>::deserialize(deserializer)?;
+                let mut v: $t = Default::default();
+                v.deserialize(&buf)
+                    .map_err(|_| D::Error::custom("invalid byte representation"))?;
+                Ok(v)
+            }
+        }
+    };
+}
+
 macro_rules! add_op_impl {
     ($t:ty, $add_fn:ident, $sub_fn:ident, $neg_fn:ident) => {
         impl $t {
@@ -126,25 +211,64 @@ macro_rules! field_mul_op_impl {
     };
 }
 
-#[derive(Default, Debug, Clone)]
+/// Idiomatic `Add`/`Sub`/`Mul<i64>` for a ciphertext type, layered on top of its raw
+/// out-parameter `she*` FFI functions.
+macro_rules! ciphertext_ops_impl {
+    ($t:ty, $add_fn:ident, $sub_fn:ident, $mul_fn:ident) => {
+        impl<'a, 'b> ops::Add<&'b $t> for &'a $t {
+            type Output = $t;
+            fn add(self, rhs: &'b $t) -> $t {
+                let mut z: $t = Default::default();
+                if unsafe { $add_fn(&mut z, self, rhs) } != 0 {
+                    panic!("add");
+                }
+                z
+            }
+        }
+
+        impl<'a, 'b> ops::Sub<&'b $t> for &'a $t {
+            type Output = $t;
+            fn sub(self, rhs: &'b $t) -> $t {
+                let mut z: $t = Default::default();
+                if unsafe { $sub_fn(&mut z, self, rhs) } != 0 {
+                    panic!("sub");
+                }
+                z
+            }
+        }
+
+        impl ops::Mul<i64> for &$t {
+            type Output = $t;
+            fn mul(self, rhs: i64) -> $t {
+                let mut z: $t = Default::default();
+                if unsafe { $mul_fn(&mut z, self, rhs) } != 0 {
+                    panic!("mul");
+                }
+                z
+            }
+        }
+    };
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct Fp {
     d: [u64; MCLBN_FP_UNIT_SIZE],
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct Fr {
     d: [u64; MCLBN_FR_UNIT_SIZE],
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct Fp2 {
     d: [Fp; 2],
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct G1 {
     pub x: Fp,
@@ -152,7 +276,7 @@ pub struct G1 {
     pub z: Fp,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct G2 {
     pub x: Fp2,
@@ -160,20 +284,20 @@ pub struct G2 {
     pub z: Fp2,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct GT {
     d: [Fp; 12],
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct SecretKey {
     pub x: Fr,
     pub y: Fr,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 #[allow(non_snake_case)]
 #[allow(non_camel_case_types)]
@@ -182,7 +306,33 @@ pub struct PublicKey {
     pub yQ: G2,
 }
 
-#[derive(Default, Debug, Clone)]
+impl PublicKey {
+    pub fn encrypt_g1(&self, m: i64) -> CipherTextG1 {
+        let mut c: CipherTextG1 = Default::default();
+        if unsafe { sheEncG1(&mut c, self, m) } != 0 {
+            panic!("encrypt_g1");
+        }
+        c
+    }
+
+    pub fn encrypt_g2(&self, m: i64) -> CipherTextG2 {
+        let mut c: CipherTextG2 = Default::default();
+        if unsafe { sheEncG2(&mut c, self, m) } != 0 {
+            panic!("encrypt_g2");
+        }
+        c
+    }
+
+    pub fn encrypt_gt(&self, m: i64) -> CipherTextGT {
+        let mut c: CipherTextGT = Default::default();
+        if unsafe { sheEncGT(&mut c, self, m) } != 0 {
+            panic!("encrypt_gt");
+        }
+        c
+    }
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 #[allow(non_snake_case)]
 pub struct CipherTextG1 {
@@ -190,7 +340,7 @@ pub struct CipherTextG1 {
     pub T: G1,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 #[allow(non_snake_case)]
 pub struct CipherTextG2 {
@@ -198,13 +348,88 @@ pub struct CipherTextG2 {
     pub T: G2,
 }
 
-#[derive(Default, Debug, Clone)]
+#[derive(Default, Debug, Clone, PartialEq)]
 #[repr(C)]
 pub struct CipherTextGT {
     pub g: [GT; 4],
 }
 
 common_impl![SecretKey];
+
+impl SecretKey {
+    /// Deterministically derive this key's `x`/`y` components from a 32-byte seed by
+    /// hashing it (with domain-separated tags) into the curve's scalar field, so the
+    /// same seed always yields the same key. Analogous to `sheSecretKeySetByCSPRNG`,
+    /// but reproducible rather than drawing from the system RNG.
+    pub fn set_by_seed(&mut self, seed: &[u8; 32]) -> Result<(), SheError> {
+        let mut buf = [0u8; 33];
+        buf[..32].copy_from_slice(seed);
+        buf[32] = 0;
+        let rx = unsafe { mclBnFr_setHashOf(&mut self.x, buf.as_ptr(), buf.len()) };
+        buf[32] = 1;
+        let ry = unsafe { mclBnFr_setHashOf(&mut self.y, buf.as_ptr(), buf.len()) };
+        if rx == 0 && ry == 0 {
+            Ok(())
+        } else {
+            Err(SheError::KeyGenFailed)
+        }
+    }
+
+    pub fn from_seed(seed: &[u8; 32]) -> Result<SecretKey, SheError> {
+        let mut sec = SecretKey::zero();
+        sec.set_by_seed(seed)?;
+        Ok(sec)
+    }
+
+    /// Draw `x`/`y` from the system CSPRNG. Prefer this over [`SecretKey::from_seed`]
+    /// unless the key needs to be reproducible.
+    pub fn set_by_csprng(&mut self) -> Result<(), SheError> {
+        if unsafe { sheSecretKeySetByCSPRNG(self) } == 0 {
+            Ok(())
+        } else {
+            Err(SheError::KeyGenFailed)
+        }
+    }
+
+    pub fn new() -> Result<SecretKey, SheError> {
+        let mut sec = SecretKey::zero();
+        sec.set_by_csprng()?;
+        Ok(sec)
+    }
+
+    pub fn get_public_key(&self) -> PublicKey {
+        let mut pubkey: PublicKey = Default::default();
+        unsafe { sheGetPublicKey(&mut pubkey, self) };
+        pubkey
+    }
+
+    pub fn decrypt_g1(&self, c: &CipherTextG1) -> Result<i64, SheError> {
+        let mut m: i64 = 0;
+        if unsafe { sheDecG1(&mut m, self, c) } == 0 {
+            Ok(m)
+        } else {
+            Err(SheError::DecryptOutOfRange)
+        }
+    }
+
+    pub fn decrypt_g2(&self, c: &CipherTextG2) -> Result<i64, SheError> {
+        let mut m: i64 = 0;
+        if unsafe { sheDecG2(&mut m, self, c) } == 0 {
+            Ok(m)
+        } else {
+            Err(SheError::DecryptOutOfRange)
+        }
+    }
+
+    pub fn decrypt_gt(&self, c: &CipherTextGT) -> Result<i64, SheError> {
+        let mut m: i64 = 0;
+        if unsafe { sheDecGT(&mut m, self, c) } == 0 {
+            Ok(m)
+        } else {
+            Err(SheError::DecryptOutOfRange)
+        }
+    }
+}
 /*
 serialize_impl![
     Fp,
@@ -214,6 +439,190 @@ serialize_impl![
 ];
 */
 
-pub fn init(curve: CurveType) -> bool {
-    unsafe { sheInit(curve as c_int, MCLBN_COMPILED_TIME_VAR) == 0 }
+serialize_impl![SecretKey, SEC_SIZE, sheSecretKeySerialize, sheSecretKeyDeserialize];
+serialize_impl![PublicKey, PUB_SIZE, shePublicKeySerialize, shePublicKeyDeserialize];
+serialize_impl![
+    CipherTextG1,
+    G1_CIPHER_SIZE,
+    sheCipherTextG1Serialize,
+    sheCipherTextG1Deserialize
+];
+serialize_impl![
+    CipherTextG2,
+    G2_CIPHER_SIZE,
+    sheCipherTextG2Serialize,
+    sheCipherTextG2Deserialize
+];
+serialize_impl![
+    CipherTextGT,
+    GT_CIPHER_SIZE,
+    sheCipherTextGTSerialize,
+    sheCipherTextGTDeserialize
+];
+
+serde_impl![SecretKey];
+serde_impl![PublicKey];
+serde_impl![CipherTextG1];
+serde_impl![CipherTextG2];
+serde_impl![CipherTextGT];
+
+ciphertext_ops_impl![CipherTextG1, sheAddG1, sheSubG1, sheMulG1];
+ciphertext_ops_impl![CipherTextG2, sheAddG2, sheSubG2, sheMulG2];
+ciphertext_ops_impl![CipherTextGT, sheAddGT, sheSubGT, sheMulGT];
+
+/// The pairing product: an encrypted-G2 value times an encrypted-G1 value (`&c2 * &c1`)
+/// yields an encrypted-GT value.
+impl<'b> ops::Mul<&'b CipherTextG1> for &CipherTextG2 {
+    type Output = CipherTextGT;
+    fn mul(self, rhs: &'b CipherTextG1) -> CipherTextGT {
+        let mut z: CipherTextGT = Default::default();
+        if unsafe { sheMul(&mut z, rhs, self) } != 0 {
+            panic!("mul");
+        }
+        z
+    }
+}
+
+/// Errors surfaced by the safe wrappers in this crate, instead of a bare mcl status code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheError {
+    /// `sheInit` reported a failure, e.g. an unsupported `CurveType`.
+    InitFailed,
+    /// Key generation did not complete successfully.
+    KeyGenFailed,
+    /// A `deserialize` call was given bytes that don't decode to a valid value.
+    DeserializeFailed,
+    /// Decryption failed to recover a plaintext within the configured DLP range.
+    DecryptOutOfRange,
+    /// Configuring or loading the baby-step/giant-step DLP table failed.
+    DlpTableFailed,
+}
+
+impl fmt::Display for SheError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            SheError::InitFailed => "she: init failed",
+            SheError::KeyGenFailed => "she: key generation failed",
+            SheError::DeserializeFailed => "she: deserialize failed",
+            SheError::DecryptOutOfRange => "she: decrypted value out of configured range",
+            SheError::DlpTableFailed => "she: DLP table operation failed",
+        };
+        f.write_str(msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SheError {}
+
+pub fn init(curve: CurveType) -> Result<(), SheError> {
+    if unsafe { sheInit(curve as c_int, MCLBN_COMPILED_TIME_VAR) == 0 } {
+        Ok(())
+    } else {
+        Err(SheError::InitFailed)
+    }
+}
+
+/// Build the baby-step table (of `hash_size` precomputed entries) that `sheDecG1`/`sheDecG2`/
+/// `sheDecGT` use for their giant-step search. Must be called once before decrypting; without
+/// it, decryption of anything but tiny values fails with [`SheError::DecryptOutOfRange`]. The actual decryptable range is
+/// `[-hash_size * try_num, hash_size * try_num]`, where `try_num` is set separately via
+/// [`set_try_num`] (its mcl default is small) — this call alone does not bound the range.
+pub fn table_size(hash_size: usize) -> Result<(), SheError> {
+    if unsafe { sheSetRangeForDLP(hash_size) } == 0 {
+        Ok(())
+    } else {
+        Err(SheError::DlpTableFailed)
+    }
+}
+
+/// Bound how many giant steps decryption will try before giving up on an out-of-range plaintext.
+/// Together with [`table_size`]'s `hash_size`, this sets the decryptable range to
+/// `[-hash_size * try_num, hash_size * try_num]`.
+pub fn set_try_num(try_num: usize) -> Result<(), SheError> {
+    if unsafe { sheSetTryNum(try_num) } == 0 {
+        Ok(())
+    } else {
+        Err(SheError::DlpTableFailed)
+    }
+}
+
+/// Serialize the current DLP table so it doesn't have to be rebuilt on the next run.
+#[cfg(feature = "alloc")]
+pub fn save_dlp_table(max_buf_size: usize) -> Vec<u8> {
+    let mut buf: Vec<u8> = Vec::with_capacity(max_buf_size);
+    let n = unsafe { sheSaveTableForDLP(buf.as_mut_ptr(), max_buf_size) };
+    if n == 0 {
+        panic!("save_dlp_table");
+    }
+    unsafe {
+        buf.set_len(n);
+    }
+    buf
+}
+
+/// Load a table previously produced by [`save_dlp_table`].
+pub fn load_dlp_table(buf: &[u8]) -> Result<(), SheError> {
+    if unsafe { sheLoadTableForDLP(buf.as_ptr(), buf.len()) } > 0 {
+        Ok(())
+    } else {
+        Err(SheError::DlpTableFailed)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+
+    fn round_trip_on(curve: CurveType) {
+        init(curve).unwrap();
+
+        let sec = SecretKey::new().unwrap();
+        let mut sec2 = SecretKey::zero();
+        sec2.deserialize(&sec.serialize()).unwrap();
+        assert_eq!(sec, sec2);
+
+        let pubkey = sec.get_public_key();
+        let mut pubkey2: PublicKey = Default::default();
+        pubkey2.deserialize(&pubkey.serialize()).unwrap();
+        assert_eq!(pubkey, pubkey2);
+
+        let c1 = pubkey.encrypt_g1(42);
+        let mut c1b: CipherTextG1 = Default::default();
+        c1b.deserialize(&c1.serialize()).unwrap();
+        assert_eq!(c1, c1b);
+
+        let c2 = pubkey.encrypt_g2(42);
+        let mut c2b: CipherTextG2 = Default::default();
+        c2b.deserialize(&c2.serialize()).unwrap();
+        assert_eq!(c2, c2b);
+
+        let cgt = pubkey.encrypt_gt(42);
+        let mut cgtb: CipherTextGT = Default::default();
+        cgtb.deserialize(&cgt.serialize()).unwrap();
+        assert_eq!(cgt, cgtb);
+    }
+
+    #[test]
+    fn serialize_round_trip_bn254() {
+        round_trip_on(CurveType::BN254);
+    }
+
+    #[test]
+    fn serialize_round_trip_bls12_381() {
+        round_trip_on(CurveType::BLS12_381);
+    }
+
+    #[test]
+    fn same_seed_yields_same_key() {
+        init(CurveType::BN254).unwrap();
+
+        let seed = [7u8; 32];
+        let sec1 = SecretKey::from_seed(&seed).unwrap();
+        let sec2 = SecretKey::from_seed(&seed).unwrap();
+        assert_eq!(sec1.serialize(), sec2.serialize());
+
+        let pub1 = sec1.get_public_key();
+        let pub2 = sec2.get_public_key();
+        assert_eq!(pub1.serialize(), pub2.serialize());
+    }
 }